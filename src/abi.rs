@@ -0,0 +1,205 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+/// A single function/event/error parameter, including nested tuple components.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbiParam {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    #[serde(default)]
+    pub indexed: bool,
+    #[serde(default)]
+    pub components: Vec<AbiParam>,
+    #[serde(default)]
+    pub internal_type: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbiFunction {
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+    #[serde(default)]
+    pub outputs: Vec<AbiParam>,
+    #[serde(default)]
+    pub state_mutability: Option<String>,
+    /// Fields solc emits that aren't modeled above (e.g. the legacy `payable`/`constant`
+    /// booleans), kept so they still appear in the written artifact. Parameters are re-serialized
+    /// from `AbiParam`, which always writes `indexed`, `components` and `internalType`.
+    #[serde(flatten)]
+    pub extra: HashMap<String, JsonValue>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbiConstructor {
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+    #[serde(default)]
+    pub state_mutability: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, JsonValue>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbiFallback {
+    #[serde(default)]
+    pub state_mutability: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, JsonValue>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbiReceive {
+    #[serde(default)]
+    pub state_mutability: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, JsonValue>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbiEvent {
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+    #[serde(default)]
+    pub anonymous: bool,
+    #[serde(flatten)]
+    pub extra: HashMap<String, JsonValue>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbiError {
+    pub name: String,
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, JsonValue>,
+}
+
+/// One entry of a contract's ABI, tagged by its `type` field. Replaces poking at raw JSON with
+/// compile-time-checked access to inputs, outputs and mutability.
+///
+/// `(De)serialize` are implemented by hand rather than derived: the `#[serde(other)]` catch-all
+/// a derived internally-tagged enum would need can only be a unit variant, which would discard
+/// whatever solc actually sent for an entry type this crate doesn't model yet. `Unknown` instead
+/// carries the original `Value` so those entries still round-trip into the written artifact.
+#[derive(Debug, Clone)]
+pub enum AbiEntry {
+    Function(AbiFunction),
+    Constructor(AbiConstructor),
+    Fallback(AbiFallback),
+    Receive(AbiReceive),
+    Event(AbiEvent),
+    Error(AbiError),
+    /// Anything solc tags with a `type` outside the six kinds above (future ABI additions,
+    /// vendor extensions), kept as-is rather than dropped or re-tagged as `"unknown"`.
+    Unknown(JsonValue),
+}
+
+impl<'de> serde::Deserialize<'de> for AbiEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut value: JsonValue = serde::Deserialize::deserialize(deserializer)?;
+        let entry_type = value.get("type").and_then(JsonValue::as_str).map(str::to_string);
+        if let JsonValue::Object(map) = &mut value {
+            map.remove("type");
+        }
+        Ok(match entry_type.as_deref() {
+            Some("function") => {
+                AbiEntry::Function(serde_json::from_value(value).map_err(serde::de::Error::custom)?)
+            }
+            Some("constructor") => {
+                AbiEntry::Constructor(serde_json::from_value(value).map_err(serde::de::Error::custom)?)
+            }
+            Some("fallback") => {
+                AbiEntry::Fallback(serde_json::from_value(value).map_err(serde::de::Error::custom)?)
+            }
+            Some("receive") => {
+                AbiEntry::Receive(serde_json::from_value(value).map_err(serde::de::Error::custom)?)
+            }
+            Some("event") => {
+                AbiEntry::Event(serde_json::from_value(value).map_err(serde::de::Error::custom)?)
+            }
+            Some("error") => {
+                AbiEntry::Error(serde_json::from_value(value).map_err(serde::de::Error::custom)?)
+            }
+            entry_type => {
+                // Put `type` back so the captured value is byte-for-byte what solc sent.
+                if let (Some(entry_type), JsonValue::Object(map)) = (entry_type, &mut value) {
+                    map.insert("type".to_string(), JsonValue::String(entry_type.to_string()));
+                }
+                AbiEntry::Unknown(value)
+            }
+        })
+    }
+}
+
+impl serde::Serialize for AbiEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        fn tagged<T: serde::Serialize>(entry_type: &str, inner: &T) -> JsonValue {
+            let mut value = serde_json::to_value(inner).unwrap_or(JsonValue::Null);
+            if let JsonValue::Object(map) = &mut value {
+                map.insert("type".to_string(), JsonValue::String(entry_type.to_string()));
+            }
+            value
+        }
+        let value = match self {
+            AbiEntry::Function(inner) => tagged("function", inner),
+            AbiEntry::Constructor(inner) => tagged("constructor", inner),
+            AbiEntry::Fallback(inner) => tagged("fallback", inner),
+            AbiEntry::Receive(inner) => tagged("receive", inner),
+            AbiEntry::Event(inner) => tagged("event", inner),
+            AbiEntry::Error(inner) => tagged("error", inner),
+            AbiEntry::Unknown(value) => value.clone(),
+        };
+        serde::Serialize::serialize(&value, serializer)
+    }
+}
+
+pub type Abi = Vec<AbiEntry>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_a_known_entry_including_legacy_fields() {
+        let raw = serde_json::json!({
+            "type": "function",
+            "name": "transfer",
+            "inputs": [],
+            "outputs": [],
+            "stateMutability": "nonpayable",
+            "payable": false,
+            "constant": false
+        });
+        let entry: AbiEntry = serde_json::from_value(raw.clone()).unwrap();
+        assert!(matches!(entry, AbiEntry::Function(_)));
+        assert_eq!(serde_json::to_value(&entry).unwrap(), raw);
+    }
+
+    #[test]
+    fn preserves_unrecognized_entry_types_verbatim() {
+        let raw = serde_json::json!({
+            "type": "userDefinedValueType",
+            "name": "Foo",
+            "underlyingType": "uint256"
+        });
+        let entry: AbiEntry = serde_json::from_value(raw.clone()).unwrap();
+        assert!(matches!(entry, AbiEntry::Unknown(_)));
+        assert_eq!(serde_json::to_value(&entry).unwrap(), raw);
+    }
+}
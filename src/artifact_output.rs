@@ -0,0 +1,154 @@
+use crate::linking::{link_bytecode, BytecodeObject, Libraries};
+use crate::{SolcContract, SolcSource};
+use serde_json::{json, Value as JsonValue};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A single compiled contract, tagged with the source path and contract name so callers (e.g.
+/// the compilation cache) can track it without having to inspect the format-specific `body`.
+pub struct Artifact {
+    pub source_path: String,
+    pub contract_name: String,
+    pub body: JsonValue,
+}
+
+/// Produces artifact files in whatever shape downstream tooling expects. Swapping the
+/// implementation lets `solc-rs` feed truffle, hardhat, or a CI type-checking pipeline without
+/// forking the crate.
+pub trait ArtifactOutput {
+    /// Builds the JSON body for a single compiled contract, linking any library placeholders
+    /// it references against `libraries` along the way.
+    fn artifact(
+        &self,
+        path: &str,
+        name: &str,
+        source: &str,
+        solc_source: &SolcSource,
+        contract: &SolcContract,
+        libraries: &Libraries,
+    ) -> JsonValue;
+
+    /// Persists every artifact to `out_dir`. The default writes one `<contract_name>.json`
+    /// file per artifact; override for formats that lay things out differently, or that don't
+    /// write anything at all.
+    fn write_all(&self, artifacts: &[Artifact], out_dir: &Path) {
+        for artifact in artifacts {
+            let json = serde_json::to_string_pretty(&artifact.body)
+                .expect("Error serializing solidity artifact");
+            let mut path = PathBuf::from(out_dir);
+            path.push(&artifact.contract_name);
+            path.set_extension("json");
+            fs::write(path.as_path(), json).expect("Error writing solidity artifact");
+        }
+    }
+}
+
+/// The original artifact shape: one JSON file per contract with bytecode, ABI, AST and source
+/// maps inlined, matching what `truffle-contract` expects.
+pub struct TruffleOutput;
+
+impl ArtifactOutput for TruffleOutput {
+    fn artifact(
+        &self,
+        path: &str,
+        name: &str,
+        source: &str,
+        solc_source: &SolcSource,
+        contract: &SolcContract,
+        libraries: &Libraries,
+    ) -> JsonValue {
+        let (bytecode, link_references) = link_bytecode(
+            &contract.evm.bytecode.object,
+            &contract.evm.bytecode.link_references,
+            libraries,
+        );
+        let (deployed_bytecode, deployed_link_references) = link_bytecode(
+            &contract.evm.deployed_bytecode.object,
+            &contract.evm.deployed_bytecode.link_references,
+            libraries,
+        );
+        json!({
+            "contractName": name,
+            "fileName": Path::new(path).file_name().unwrap().to_str().unwrap(),
+            "sourcePath": path,
+            "source": source,
+            "bytecode": bytecode.as_str(),
+            "deployedBytecode": deployed_bytecode.as_str(),
+            "sourceMap": contract.evm.bytecode.source_map,
+            "deployedSourceMap": contract.evm.deployed_bytecode.source_map,
+            "linkReferences": link_references,
+            "deployedLinkReferences": deployed_link_references,
+            "abi": contract.abi,
+            "ast": solc_source.ast,
+        })
+    }
+}
+
+/// Hardhat-compatible artifact (`hh-sol-artifact-1`). No AST, no source, no source maps - just
+/// what `hardhat-ethers`/`ethers` need to deploy and interact with the contract.
+pub struct HardhatOutput;
+
+impl ArtifactOutput for HardhatOutput {
+    fn artifact(
+        &self,
+        path: &str,
+        name: &str,
+        _source: &str,
+        _solc_source: &SolcSource,
+        contract: &SolcContract,
+        libraries: &Libraries,
+    ) -> JsonValue {
+        let (bytecode, link_references) = link_bytecode(
+            &contract.evm.bytecode.object,
+            &contract.evm.bytecode.link_references,
+            libraries,
+        );
+        let (deployed_bytecode, deployed_link_references) = link_bytecode(
+            &contract.evm.deployed_bytecode.object,
+            &contract.evm.deployed_bytecode.link_references,
+            libraries,
+        );
+        json!({
+            "_format": "hh-sol-artifact-1",
+            "contractName": name,
+            "sourceName": path,
+            "abi": contract.abi,
+            "bytecode": prefixed_hex(&bytecode),
+            "deployedBytecode": prefixed_hex(&deployed_bytecode),
+            "linkReferences": link_references,
+            "deployedLinkReferences": deployed_link_references,
+        })
+    }
+}
+
+/// Hardhat always wants a `0x`-prefixed bytecode string, but `BytecodeObject::as_str` already
+/// includes the prefix for the empty `"0x"` case (interfaces/abstract contracts) and omits it
+/// otherwise - so a bare `format!("0x{}", ...)` would double it up for empty bytecode.
+fn prefixed_hex(bytecode: &BytecodeObject) -> String {
+    let object = bytecode.as_str();
+    if object.starts_with("0x") {
+        object.to_string()
+    } else {
+        format!("0x{}", object)
+    }
+}
+
+/// Compiles without writing anything to disk. Useful for CI jobs that only want to know
+/// whether the contracts type-check.
+pub struct Nothing;
+
+impl ArtifactOutput for Nothing {
+    fn artifact(
+        &self,
+        _path: &str,
+        _name: &str,
+        _source: &str,
+        _solc_source: &SolcSource,
+        _contract: &SolcContract,
+        _libraries: &Libraries,
+    ) -> JsonValue {
+        JsonValue::Null
+    }
+
+    fn write_all(&self, _artifacts: &[Artifact], _out_dir: &Path) {}
+}
@@ -0,0 +1,347 @@
+use serde_json::{json, Value as JsonValue};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Location of the hardhat-compatible solidity files cache, relative to the project root.
+pub const CACHE_PATH: &str = "./build/solidity-files-cache.json";
+
+/// Everything we need to remember about a single source file between runs in order to
+/// decide whether it needs to be fed into solc again.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedFile {
+    pub last_modification_date: u64,
+    pub content_hash: String,
+    pub source_name: String,
+    pub solc_version: String,
+    pub imports: Vec<String>,
+    /// Names of the contracts this file produced, so we can tell whether their artifact JSON
+    /// is still on disk without re-parsing solc output.
+    pub artifact_names: Vec<String>,
+}
+
+/// On-disk cache of previously compiled sources, keyed by source path. Mirrors the shape of
+/// hardhat's `solidity-files-cache.json` closely enough that tooling built against that format
+/// can inspect it.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SolFilesCache {
+    #[serde(rename = "_format")]
+    pub format: String,
+    pub files: HashMap<String, CachedFile>,
+    /// Fingerprint of the solc settings (evm version, optimizer, output selection, ...) used
+    /// to produce `files`. Any change here invalidates the whole cache.
+    pub settings_fingerprint: String,
+}
+
+const CACHE_FORMAT: &str = "solc-rs-cache-1";
+
+/// Reads the cache from `path`, returning an empty cache if it doesn't exist or fails to parse.
+pub fn read_cache(path: &Path) -> SolFilesCache {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Serializes `cache` to `path`, creating the parent directory if needed.
+pub fn write_cache(cache: &SolFilesCache, path: &Path) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("Error creating cache dir");
+    }
+    let json = serde_json::to_string_pretty(cache).expect("Error serializing solidity files cache");
+    fs::write(path, json).expect("Error writing solidity files cache");
+}
+
+/// Hashes file contents so we can detect edits regardless of mtime granularity or checkouts
+/// that don't preserve timestamps.
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", md5::compute(content.as_bytes()))
+}
+
+/// Fingerprints the subset of solc settings that affect every source file's compiled output.
+/// Changing any of these must invalidate the entire cache rather than just the edited files.
+pub fn settings_fingerprint(
+    evm_version: &str,
+    optimizer_enabled: bool,
+    optimizer_runs: u32,
+    output_selection: &JsonValue,
+) -> String {
+    let fingerprint_source = json!({
+        "evmVersion": evm_version,
+        "optimizerEnabled": optimizer_enabled,
+        "optimizerRuns": optimizer_runs,
+        "outputSelection": output_selection,
+    });
+    format!("{:x}", md5::compute(fingerprint_source.to_string().as_bytes()))
+}
+
+/// Returns the source file's last modification time as seconds since the unix epoch, or 0 if
+/// it can't be determined (e.g. on filesystems without mtime support).
+pub fn last_modification_date(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Extracts the raw import paths (as written in the source, not yet resolved) from a solidity
+/// file's contents. This is a lightweight scan rather than a full parse, since all we need is
+/// the dependency edges for cache invalidation.
+pub fn extract_imports(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let trimmed = line.trim_start();
+            if !trimmed.starts_with("import") {
+                return None;
+            }
+            let quote_start = trimmed.find(|c| c == '"' || c == '\'')?;
+            let quote = trimmed[quote_start..].chars().next()?;
+            let rest = &trimmed[quote_start + 1..];
+            let quote_end = rest.find(quote)?;
+            Some(rest[..quote_end].to_string())
+        })
+        .collect()
+}
+
+/// Normalizes a source path into the canonical `./a/b/c.sol` form used as map keys throughout
+/// this crate: components are collapsed (`.` dropped, `..` pops the last segment) and the result
+/// always gets a `./` prefix. This is what lets a source key discovered by globbing a
+/// configurable `sources_dir` (which may or may not itself start with `./`) line up with the same
+/// path as resolved from a relative `import` statement.
+pub fn normalize_path(path: &str) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for component in Path::new(path).components() {
+        match component.as_os_str().to_str().unwrap_or("") {
+            "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part.to_string()),
+        }
+    }
+    format!("./{}", parts.join("/"))
+}
+
+/// Resolves an import against the importing file's path into the same `./contracts/...`-style
+/// key used elsewhere for source paths. Non-relative imports (e.g. `@openzeppelin/...`) are
+/// first tried against `remappings`; anything left unresolved is returned untouched, since it
+/// doesn't correspond to a key in our sources map.
+pub fn resolve_import(importer_path: &str, import: &str, remappings: &[String]) -> String {
+    if !import.starts_with('.') {
+        return crate::config::apply_remappings(import, remappings).unwrap_or_else(|| import.to_string());
+    }
+    let base = Path::new(importer_path)
+        .parent()
+        .unwrap_or_else(|| Path::new("."));
+    normalize_path(&base.join(import).to_string_lossy())
+}
+
+/// Given the newly-scanned sources and import graph, determines which source paths must be
+/// (re)compiled: those with a changed content hash, those missing a written artifact, those
+/// whose resolved solc version differs from the cached one, and anything that transitively
+/// imports one of those (so editing a dependency always recompiles its dependents).
+pub fn dirty_paths(
+    sources: &HashMap<String, String>,
+    imports_by_path: &HashMap<String, Vec<String>>,
+    cache: &SolFilesCache,
+    artifacts_dir: &Path,
+    resolved_solc_versions: &HashMap<String, String>,
+    remappings: &[String],
+) -> HashSet<String> {
+    let mut dirty: HashSet<String> = HashSet::new();
+
+    for (path, content) in sources {
+        let hash = content_hash(content);
+        let cached = cache.files.get(path);
+        let unchanged = cached
+            .map(|cached| cached.content_hash == hash)
+            .unwrap_or(false);
+        let artifacts_present = cached
+            .map(|cached| {
+                !cached.artifact_names.is_empty()
+                    && cached
+                        .artifact_names
+                        .iter()
+                        .all(|name| artifacts_dir.join(name).with_extension("json").exists())
+            })
+            .unwrap_or(false);
+        let same_solc_version = cached
+            .zip(resolved_solc_versions.get(path))
+            .map(|(cached, resolved)| &cached.solc_version == resolved)
+            .unwrap_or(false);
+        if !unchanged || !artifacts_present || !same_solc_version {
+            dirty.insert(path.clone());
+        }
+    }
+
+    // Build the reverse dependency graph (dependency -> dependents) so we can propagate
+    // dirtiness from an edited file to everything that imports it.
+    let mut dependents: HashMap<String, Vec<String>> = HashMap::new();
+    for (path, imports) in imports_by_path {
+        for import in imports {
+            let resolved = resolve_import(path, import, remappings);
+            dependents.entry(resolved).or_default().push(path.clone());
+        }
+    }
+
+    let mut queue: Vec<String> = dirty.iter().cloned().collect();
+    while let Some(path) = queue.pop() {
+        if let Some(deps) = dependents.get(&path) {
+            for dependent in deps {
+                if dirty.insert(dependent.clone()) {
+                    queue.push(dependent.clone());
+                }
+            }
+        }
+    }
+
+    dirty
+}
+
+/// Builds the cache entry for a freshly-compiled (or freshly-confirmed-unchanged) source file.
+pub fn build_cached_file(
+    path: &Path,
+    source_name: &str,
+    content: &str,
+    solc_version: &str,
+    imports: Vec<String>,
+    artifact_names: Vec<String>,
+) -> CachedFile {
+    CachedFile {
+        last_modification_date: last_modification_date(path),
+        content_hash: content_hash(content),
+        source_name: source_name.to_string(),
+        solc_version: solc_version.to_string(),
+        imports,
+        artifact_names,
+    }
+}
+
+impl SolFilesCache {
+    pub fn new(settings_fingerprint: String) -> Self {
+        SolFilesCache {
+            format: CACHE_FORMAT.to_string(),
+            files: HashMap::new(),
+            settings_fingerprint,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_path_agrees_with_resolve_import_regardless_of_leading_dot_slash() {
+        // A `sources_dir` of "contracts" (no leading "./") must still produce the same key a
+        // relative import resolves to, or import edges never match up.
+        assert_eq!(normalize_path("contracts/A.sol"), "./contracts/A.sol");
+        assert_eq!(normalize_path("./contracts/A.sol"), "./contracts/A.sol");
+        assert_eq!(resolve_import("./contracts/A.sol", "./B.sol", &[]), "./contracts/B.sol");
+        assert_eq!(resolve_import("contracts/A.sol", "./B.sol", &[]), "./contracts/B.sol");
+    }
+
+    /// Each test gets its own artifacts dir under the system temp dir, named after the test so
+    /// concurrent test runs don't collide; callers are responsible for cleaning it up.
+    fn temp_artifacts_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("solc-rs-cache-test-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn editing_a_dependency_marks_its_dependents_dirty() {
+        let artifacts_dir = temp_artifacts_dir("dependency-propagation");
+        fs::write(artifacts_dir.join("A.json"), "{}").unwrap();
+        fs::write(artifacts_dir.join("B.json"), "{}").unwrap();
+
+        let a_content = "import \"./B.sol\";\ncontract A {}\n".to_string();
+        let b_content = "contract B { uint x; }\n".to_string();
+        let mut sources = HashMap::new();
+        sources.insert("./contracts/A.sol".to_string(), a_content.clone());
+        sources.insert("./contracts/B.sol".to_string(), b_content.clone());
+        let imports_by_path: HashMap<String, Vec<String>> = sources
+            .iter()
+            .map(|(path, content)| (path.clone(), extract_imports(content)))
+            .collect();
+
+        let mut cache = SolFilesCache::new("fingerprint".to_string());
+        cache.files.insert(
+            "./contracts/A.sol".to_string(),
+            build_cached_file(
+                Path::new("./contracts/A.sol"),
+                "./contracts/A.sol",
+                &a_content, // unchanged since last run
+                "0.8.19",
+                vec!["./B.sol".to_string()],
+                vec!["A".to_string()],
+            ),
+        );
+        cache.files.insert(
+            "./contracts/B.sol".to_string(),
+            build_cached_file(
+                Path::new("./contracts/B.sol"),
+                "./contracts/B.sol",
+                "contract B { /* old body */ }\n", // differs from current b_content
+                "0.8.19",
+                vec![],
+                vec!["B".to_string()],
+            ),
+        );
+
+        let mut resolved_solc_versions = HashMap::new();
+        resolved_solc_versions.insert("./contracts/A.sol".to_string(), "0.8.19".to_string());
+        resolved_solc_versions.insert("./contracts/B.sol".to_string(), "0.8.19".to_string());
+
+        let dirty = dirty_paths(&sources, &imports_by_path, &cache, &artifacts_dir, &resolved_solc_versions, &[]);
+
+        assert!(dirty.contains("./contracts/B.sol"), "changed file itself must be dirty");
+        assert!(
+            dirty.contains("./contracts/A.sol"),
+            "unchanged importer of a changed dependency must be dirty too"
+        );
+
+        fs::remove_dir_all(&artifacts_dir).unwrap();
+    }
+
+    #[test]
+    fn missing_artifact_file_marks_an_otherwise_unchanged_source_dirty() {
+        let artifacts_dir = temp_artifacts_dir("missing-artifact");
+        // Deliberately don't write C.json, simulating an artifact deleted between runs.
+
+        let content = "contract C {}\n".to_string();
+        let mut sources = HashMap::new();
+        sources.insert("./contracts/C.sol".to_string(), content.clone());
+        let imports_by_path: HashMap<String, Vec<String>> =
+            [("./contracts/C.sol".to_string(), Vec::new())].into_iter().collect();
+
+        let mut cache = SolFilesCache::new("fingerprint".to_string());
+        cache.files.insert(
+            "./contracts/C.sol".to_string(),
+            build_cached_file(
+                Path::new("./contracts/C.sol"),
+                "./contracts/C.sol",
+                &content,
+                "0.8.19",
+                vec![],
+                vec!["C".to_string()],
+            ),
+        );
+
+        let mut resolved_solc_versions = HashMap::new();
+        resolved_solc_versions.insert("./contracts/C.sol".to_string(), "0.8.19".to_string());
+
+        let dirty = dirty_paths(&sources, &imports_by_path, &cache, &artifacts_dir, &resolved_solc_versions, &[]);
+
+        assert!(dirty.contains("./contracts/C.sol"));
+
+        fs::remove_dir_all(&artifacts_dir).unwrap();
+    }
+}
@@ -0,0 +1,192 @@
+use serde_json::{json, Value as JsonValue};
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Default location of the project config file, relative to the project root.
+pub const CONFIG_PATH: &str = "./solc-rs.json";
+
+/// EVM versions accepted by solc's `settings.evmVersion`, oldest to newest. Deserialized via
+/// `FromStr` so the config file accepts the same lowercase spelling solc itself uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum EvmVersion {
+    Homestead,
+    TangerineWhistle,
+    SpuriousDragon,
+    Byzantium,
+    Constantinople,
+    Petersburg,
+    Istanbul,
+    Berlin,
+    London,
+    Paris,
+    Shanghai,
+}
+
+impl Default for EvmVersion {
+    /// Matches the hardcoded value this crate used before it became configurable.
+    fn default() -> Self {
+        EvmVersion::Byzantium
+    }
+}
+
+impl fmt::Display for EvmVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            EvmVersion::Homestead => "homestead",
+            EvmVersion::TangerineWhistle => "tangerineWhistle",
+            EvmVersion::SpuriousDragon => "spuriousDragon",
+            EvmVersion::Byzantium => "byzantium",
+            EvmVersion::Constantinople => "constantinople",
+            EvmVersion::Petersburg => "petersburg",
+            EvmVersion::Istanbul => "istanbul",
+            EvmVersion::Berlin => "berlin",
+            EvmVersion::London => "london",
+            EvmVersion::Paris => "paris",
+            EvmVersion::Shanghai => "shanghai",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for EvmVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "homestead" => Ok(EvmVersion::Homestead),
+            "tangerinewhistle" => Ok(EvmVersion::TangerineWhistle),
+            "spuriousdragon" => Ok(EvmVersion::SpuriousDragon),
+            "byzantium" => Ok(EvmVersion::Byzantium),
+            "constantinople" => Ok(EvmVersion::Constantinople),
+            "petersburg" => Ok(EvmVersion::Petersburg),
+            "istanbul" => Ok(EvmVersion::Istanbul),
+            "berlin" => Ok(EvmVersion::Berlin),
+            "london" => Ok(EvmVersion::London),
+            "paris" => Ok(EvmVersion::Paris),
+            "shanghai" => Ok(EvmVersion::Shanghai),
+            other => Err(format!("unknown EVM version '{}'", other)),
+        }
+    }
+}
+
+impl TryFrom<String> for EvmVersion {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<EvmVersion> for String {
+    fn from(version: EvmVersion) -> String {
+        version.to_string()
+    }
+}
+
+/// The optimizer settings solc's standard-json input expects.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizerConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_optimizer_runs")]
+    pub runs: u32,
+}
+
+fn default_optimizer_runs() -> u32 {
+    200
+}
+
+impl Default for OptimizerConfig {
+    fn default() -> Self {
+        OptimizerConfig {
+            enabled: false,
+            runs: default_optimizer_runs(),
+        }
+    }
+}
+
+fn default_sources_dir() -> String {
+    "./contracts".to_string()
+}
+
+fn default_output_dir() -> String {
+    "./build/contracts".to_string()
+}
+
+fn default_output_selection() -> JsonValue {
+    json!({
+      "*": {
+        "": ["ast"],
+        "*": [
+          "abi",
+          "evm.bytecode.object",
+          "evm.bytecode.linkReferences",
+          "evm.deployedBytecode.object",
+          "evm.deployedBytecode.linkReferences",
+        ],
+      },
+    })
+}
+
+/// Project-level solc settings, deserializable from `solc-rs.json` so real projects can build
+/// without editing the crate's source.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectConfig {
+    #[serde(default = "default_sources_dir")]
+    pub sources_dir: String,
+    #[serde(default = "default_output_dir")]
+    pub output_dir: String,
+    #[serde(default)]
+    pub evm_version: EvmVersion,
+    #[serde(default)]
+    pub optimizer: OptimizerConfig,
+    /// Raw solc remapping strings, e.g. `@openzeppelin/=node_modules/@openzeppelin/`.
+    #[serde(default)]
+    pub remappings: Vec<String>,
+    #[serde(default = "default_output_selection")]
+    pub output_selection: JsonValue,
+}
+
+impl Default for ProjectConfig {
+    fn default() -> Self {
+        ProjectConfig {
+            sources_dir: default_sources_dir(),
+            output_dir: default_output_dir(),
+            evm_version: EvmVersion::default(),
+            optimizer: OptimizerConfig::default(),
+            remappings: Vec::new(),
+            output_selection: default_output_selection(),
+        }
+    }
+}
+
+/// Loads the project config from `path`, falling back to defaults (matching this crate's
+/// previous hardcoded behavior) if the file doesn't exist or fails to parse.
+pub fn load_config(path: &Path) -> ProjectConfig {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Applies the first matching remapping (`prefix=target`, optionally prefixed with a
+/// `context:`) to `import`, returning the remapped path. Returns `None` if nothing matches.
+pub fn apply_remappings(import: &str, remappings: &[String]) -> Option<String> {
+    for remapping in remappings {
+        let (prefix, target) = match remapping.split_once('=') {
+            Some(parts) => parts,
+            None => continue,
+        };
+        let prefix = prefix.rsplit(':').next().unwrap_or(prefix);
+        if let Some(rest) = import.strip_prefix(prefix) {
+            return Some(format!("{}{}", target, rest));
+        }
+    }
+    None
+}
@@ -0,0 +1,170 @@
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+
+/// Maps `sourceFile:LibraryName` (matching how solc nests `linkReferences`) to the address the
+/// library was deployed at.
+pub type Libraries = HashMap<String, String>;
+
+/// Bytecode that may still contain unresolved `__$...$__` library placeholders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BytecodeObject {
+    /// One or more library placeholders are still unresolved (defaults to `"0x"` when there's
+    /// no bytecode at all, e.g. for an interface).
+    Unlinked(String),
+    /// Every placeholder has been substituted with a real address.
+    Linked(String),
+}
+
+impl BytecodeObject {
+    pub fn as_str(&self) -> &str {
+        match self {
+            BytecodeObject::Unlinked(object) => object,
+            BytecodeObject::Linked(object) => object,
+        }
+    }
+}
+
+/// Substitutes `__$...$__` library placeholders in `object` with addresses from `libraries`,
+/// using the byte offsets solc reports in `link_references`. The placeholder is not valid hex
+/// (it's a `__$<34-char hash>$__` marker), so we splice the replacement straight into the hex
+/// *string* at `start * 2` for `length * 2` characters rather than decoding `object` as bytes.
+/// Returns the resulting bytecode alongside the subset of `link_references` that couldn't be
+/// resolved, so callers know what's still missing before the contract is deployable.
+pub fn link_bytecode(
+    object: &str,
+    link_references: &JsonValue,
+    libraries: &Libraries,
+) -> (BytecodeObject, JsonValue) {
+    if object.is_empty() {
+        return (BytecodeObject::Unlinked("0x".to_string()), json!({}));
+    }
+
+    let references = match link_references.as_object() {
+        Some(references) if !references.is_empty() => references,
+        _ => return (BytecodeObject::Linked(object.to_string()), json!({})),
+    };
+
+    let mut chars: Vec<char> = object.chars().collect();
+    let mut pending = serde_json::Map::new();
+
+    for (source_file, libs) in references {
+        let libs = match libs.as_object() {
+            Some(libs) => libs,
+            None => continue,
+        };
+        for (library_name, offsets) in libs {
+            let offsets = match offsets.as_array() {
+                Some(offsets) => offsets,
+                None => continue,
+            };
+            let key = format!("{}:{}", source_file, library_name);
+            let address_hex = libraries.get(&key).and_then(|address| {
+                let trimmed = address.trim_start_matches("0x");
+                (trimmed.len() == 40 && trimmed.chars().all(|c| c.is_ascii_hexdigit()))
+                    .then(|| trimmed.to_lowercase())
+            });
+
+            let unresolved: Vec<JsonValue> = match &address_hex {
+                Some(address_hex) => offsets
+                    .iter()
+                    .filter_map(|offset| {
+                        let start = offset["start"].as_u64().unwrap_or(0) as usize;
+                        let length = offset["length"].as_u64().unwrap_or(20) as usize;
+                        let in_bounds = start
+                            .checked_mul(2)
+                            .zip(length.checked_mul(2))
+                            .and_then(|(start_char, width)| {
+                                let end_char = start_char.checked_add(width)?;
+                                (end_char <= chars.len() && width <= address_hex.len())
+                                    .then_some((start_char, end_char))
+                            });
+                        match in_bounds {
+                            Some((start_char, end_char)) => {
+                                chars.splice(start_char..end_char, address_hex[..length * 2].chars());
+                                None
+                            }
+                            None => Some(offset.clone()),
+                        }
+                    })
+                    .collect(),
+                None => offsets.clone(),
+            };
+
+            if !unresolved.is_empty() {
+                pending
+                    .entry(source_file.clone())
+                    .or_insert_with(|| json!({}))
+                    .as_object_mut()
+                    .unwrap()
+                    .insert(library_name.clone(), JsonValue::Array(unresolved));
+            }
+        }
+    }
+
+    let linked_object: String = chars.into_iter().collect();
+    if pending.is_empty() {
+        (BytecodeObject::Linked(linked_object), JsonValue::Object(pending))
+    } else {
+        (BytecodeObject::Unlinked(linked_object), JsonValue::Object(pending))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// solc placeholders are `__$<34 hex chars>$__` (40 chars total, matching the 20-byte slot
+    /// they occupy) and are not valid hex themselves - real input for this function.
+    fn placeholder() -> String {
+        format!("__${}$__", "ab".repeat(17))
+    }
+
+    fn offset(start: usize, length: usize) -> JsonValue {
+        json!({ "start": start, "length": length })
+    }
+
+    #[test]
+    fn substitutes_placeholder_with_known_library_address() {
+        let object = format!("6080{}6040", placeholder());
+        let link_references = json!({
+            "contracts/Lib.sol": { "Lib": [offset(2, 20)] }
+        });
+        let mut libraries = Libraries::new();
+        let address_hex = "11".repeat(20);
+        libraries.insert("contracts/Lib.sol:Lib".to_string(), format!("0x{}", address_hex));
+
+        let (bytecode, pending) = link_bytecode(&object, &link_references, &libraries);
+
+        assert_eq!(bytecode, BytecodeObject::Linked(format!("6080{}6040", address_hex)));
+        assert_eq!(pending, json!({}));
+    }
+
+    #[test]
+    fn leaves_placeholder_unlinked_when_address_missing() {
+        let object = format!("6080{}6040", placeholder());
+        let link_references = json!({
+            "contracts/Lib.sol": { "Lib": [offset(2, 20)] }
+        });
+        let libraries = Libraries::new();
+
+        let (bytecode, pending) = link_bytecode(&object, &link_references, &libraries);
+
+        assert_eq!(bytecode, BytecodeObject::Unlinked(object));
+        assert_eq!(pending, json!({ "contracts/Lib.sol": { "Lib": [offset(2, 20)] } }));
+    }
+
+    #[test]
+    fn out_of_bounds_offset_is_left_pending_instead_of_panicking() {
+        let object = "6080".to_string();
+        let link_references = json!({
+            "contracts/Lib.sol": { "Lib": [offset(100, 20)] }
+        });
+        let mut libraries = Libraries::new();
+        libraries.insert("contracts/Lib.sol:Lib".to_string(), format!("0x{}", "11".repeat(20)));
+
+        let (bytecode, pending) = link_bytecode(&object, &link_references, &libraries);
+
+        assert_eq!(bytecode, BytecodeObject::Unlinked(object));
+        assert_eq!(pending, json!({ "contracts/Lib.sol": { "Lib": [offset(100, 20)] } }));
+    }
+}
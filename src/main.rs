@@ -1,34 +1,34 @@
 extern crate glob;
+extern crate md5;
 extern crate serde;
 extern crate serde_json;
 
+mod abi;
+mod artifact_output;
+mod cache;
+mod config;
+mod linking;
+mod versions;
+
+use abi::Abi;
+use artifact_output::{Artifact, ArtifactOutput, HardhatOutput, Nothing, TruffleOutput};
+use cache::SolFilesCache;
+use config::ProjectConfig;
+use linking::Libraries;
 use glob::glob;
 use serde_json::{json, Value as JsonValue};
 use std::collections::HashMap;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::Path;
+use std::thread;
 use std::time::Instant;
+use versions::{resolve_groups, Version};
 
 #[derive(serde::Serialize)]
 struct SolidityFile {
     content: String,
 }
 
-#[derive(serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct SolidityArtifact {
-    contract_name: String,
-    file_name: String,
-    source_path: String,
-    source: String,
-    bytecode: String,
-    deployed_bytecode: String,
-    source_map: String,
-    deployed_source_map: String,
-    abi: JsonValue,
-    ast: JsonValue,
-}
-
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
 struct SolcOutput {
     contracts: HashMap<String, HashMap<String, SolcContract>>,
@@ -37,30 +37,31 @@ struct SolcOutput {
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
-struct SolcContract {
-    evm: SolcContractEvm,
-    abi: JsonValue,
+pub(crate) struct SolcContract {
+    pub(crate) evm: SolcContractEvm,
+    pub(crate) abi: Abi,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
-struct SolcContractEvm {
-    bytecode: SolcBytecodeOutput,
-    deployed_bytecode: SolcBytecodeOutput,
+pub(crate) struct SolcContractEvm {
+    pub(crate) bytecode: SolcBytecodeOutput,
+    pub(crate) deployed_bytecode: SolcBytecodeOutput,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
-struct SolcBytecodeOutput {
-    object: String,
-    source_map: String,
-    // link_references,
+pub(crate) struct SolcBytecodeOutput {
+    pub(crate) object: String,
+    pub(crate) source_map: String,
+    #[serde(default)]
+    pub(crate) link_references: JsonValue,
     // opcodes,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
-struct SolcSource {
-    ast: JsonValue,
+pub(crate) struct SolcSource {
+    pub(crate) ast: JsonValue,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
@@ -73,19 +74,40 @@ struct SolcError {
 fn build_contract_schemas(
     output: &SolcOutput,
     sources: &HashMap<String, SolidityFile>,
-) -> Vec<SolidityArtifact> {
+    artifact_output: &dyn ArtifactOutput,
+    libraries: &Libraries,
+) -> Vec<Artifact> {
     output
         .contracts
         .iter()
         .flat_map(
             |(path, contracts): (&String, &HashMap<String, SolcContract>)| {
-                let solc_source: &SolcSource = output.sources.get(path).unwrap();
+                // `output` covers every file solc touched while resolving imports for the group,
+                // which can include paths that weren't in `sources` (e.g. requested only as an
+                // import closure dependency by a caller that doesn't want artifacts for them).
+                // Skip those rather than panicking on a lookup that was never guaranteed to hit.
+                let solc_source = match output.sources.get(path) {
+                    Some(solc_source) => solc_source,
+                    None => return Vec::new(),
+                };
+                let source = match sources.get(path) {
+                    Some(file) => &file.content,
+                    None => return Vec::new(),
+                };
                 contracts
                     .iter()
                     .map(move |(name, contract): (&String, &SolcContract)| {
-                        let ref source = sources.get(path).unwrap().content;
-                        build_contract_schema(path, name, source, solc_source, contract)
+                        build_contract_schema(
+                            path,
+                            name,
+                            source,
+                            solc_source,
+                            contract,
+                            artifact_output,
+                            libraries,
+                        )
                     })
+                    .collect::<Vec<_>>()
             },
         )
         .collect()
@@ -97,39 +119,96 @@ fn build_contract_schema(
     source: &String,
     solc_source: &SolcSource,
     solc_contract: &SolcContract,
-) -> SolidityArtifact {
-    SolidityArtifact {
-        abi: solc_contract.abi.clone(),
-        bytecode: solc_contract.evm.bytecode.object.clone(),
-        deployed_bytecode: solc_contract.evm.deployed_bytecode.object.clone(),
-        contract_name: name.clone(),
-        file_name: String::from(Path::new(path).file_name().unwrap().to_str().unwrap()),
-        ast: solc_source.ast.clone(),
+    artifact_output: &dyn ArtifactOutput,
+    libraries: &Libraries,
+) -> Artifact {
+    Artifact {
         source_path: path.clone(),
-        source: source.clone(),
-        source_map: solc_contract.evm.bytecode.source_map.clone(),
-        deployed_source_map: solc_contract.evm.deployed_bytecode.source_map.clone(),
+        contract_name: name.clone(),
+        body: artifact_output.artifact(path, name, source, solc_source, solc_contract, libraries),
+    }
+}
+
+/// Combines the `SolcOutput`s produced by compiling disjoint version groups back into one set
+/// of artifacts, since `build_contract_schemas` expects a single output covering every source.
+fn merge_solc_outputs(outputs: Vec<SolcOutput>) -> SolcOutput {
+    let mut merged = SolcOutput {
+        contracts: HashMap::new(),
+        sources: HashMap::new(),
+        errors: Vec::new(),
+    };
+    for output in outputs {
+        merged.contracts.extend(output.contracts);
+        merged.sources.extend(output.sources);
+        merged.errors.extend(output.errors);
     }
+    merged
 }
 
-fn write_contract_schemas(artifacts: &[SolidityArtifact], output_path: &Path) {
-    for artifact in artifacts {
-        let json =
-            serde_json::to_string_pretty(artifact).expect("Error serializing solidity artifact");
-        let mut path = PathBuf::from(output_path);
-        path.push(&artifact.contract_name);
-        path.set_extension("json");
-        fs::write(path.as_path(), json).expect("Error writing solidity artifact");
+/// Prints every compiler diagnostic, and reports whether any of them is fatal. Warnings are
+/// printed but don't affect the build; an entry with `severity == "error"` means the contracts
+/// that produced it didn't actually compile, even though solc still returns a 0 exit code.
+fn report_solc_errors(errors: &[SolcError]) -> bool {
+    let mut has_error = false;
+    for error in errors {
+        if error.severity == "error" {
+            has_error = true;
+            eprintln!("{}", error.formatted_message);
+        } else {
+            println!("{}", error.formatted_message);
+        }
+    }
+    has_error
+}
+
+/// Returns the solc releases available locally, parsed into `Version`s. Unparseable entries
+/// (shouldn't normally occur) are skipped rather than failing the whole build.
+fn installed_versions() -> Vec<Version> {
+    solc::installed_versions()
+        .iter()
+        .filter_map(|v| Version::parse(v))
+        .collect()
+}
+
+/// Selects which `ArtifactOutput` to feed compiled contracts into, based on the
+/// `SOLC_RS_ARTIFACT_FORMAT` environment variable: `truffle` (default), `hardhat`, or `none`.
+fn select_artifact_output() -> Box<dyn ArtifactOutput> {
+    match std::env::var("SOLC_RS_ARTIFACT_FORMAT").as_deref() {
+        Ok("hardhat") => Box::new(HardhatOutput),
+        Ok("none") => Box::new(Nothing),
+        _ => Box::new(TruffleOutput),
     }
 }
 
-fn get_solidity_sources() -> HashMap<String, SolidityFile> {
-    glob("./contracts/**/*.sol")
+/// Parses the `SOLC_RS_LIBRARIES` environment variable into a library address map. Expected
+/// format is a comma-separated list of `sourceFile:LibraryName=0xAddress` entries, e.g.
+/// `./contracts/Lib.sol:Lib=0x0000000000000000000000000000000000000001`. The source file part
+/// must use the `./`-prefixed source keys this crate hands solc.
+fn parse_libraries_env() -> Libraries {
+    std::env::var("SOLC_RS_LIBRARIES")
+        .ok()
+        .map(|value| {
+            value
+                .split(',')
+                .filter_map(|entry| {
+                    let (key, address) = entry.split_once('=')?;
+                    Some((key.trim().to_string(), address.trim().to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn get_solidity_sources(sources_dir: &str) -> HashMap<String, SolidityFile> {
+    glob(&format!("{}/**/*.sol", sources_dir))
         .expect("Error parsing contracts glob")
         .map(|path: glob::GlobResult| {
             let path = path.expect("Error accessing local path");
             let content = fs::read_to_string(&path).expect("Error reading contract file");
-            let filename = String::from(path.to_str().unwrap());
+            // Normalize to the same `./a/b.sol` form `resolve_import` produces, regardless of
+            // whether the configured `sources_dir` itself starts with `./`, so import edges
+            // match up against these keys during version grouping and dirty-dependency tracking.
+            let filename = cache::normalize_path(path.to_str().unwrap());
             (filename, SolidityFile { content })
         })
         .into_iter()
@@ -138,25 +217,18 @@ fn get_solidity_sources() -> HashMap<String, SolidityFile> {
 
 fn build_solc_input_json(
     sources: &HashMap<String, SolidityFile>,
-    evm_version: &str,
+    config: &ProjectConfig,
 ) -> serde_json::Value {
     json!({
       "language": "Solidity",
       "settings": {
-        "evmVersion": evm_version,
+        "evmVersion": config.evm_version.to_string(),
         "optimizer": {
-          "enabled": false
+          "enabled": config.optimizer.enabled,
+          "runs": config.optimizer.runs,
         },
-        "outputSelection": {
-          "*": {
-            "": ["ast"],
-            "*": [
-              "abi",
-              "evm.bytecode.object",
-              "evm.deployedBytecode.object",
-            ],
-          },
-        }
+        "outputSelection": config.output_selection,
+        "remappings": config.remappings,
       },
       "sources": sources
     })
@@ -166,22 +238,142 @@ pub fn main() {
     // Profiling
     let now = Instant::now();
 
+    // Load project settings, falling back to this crate's previous hardcoded defaults.
+    let config = config::load_config(Path::new(config::CONFIG_PATH));
+
     // Create list of solidity sources with content
-    let sources: HashMap<String, SolidityFile> = get_solidity_sources();
+    let sources: HashMap<String, SolidityFile> = get_solidity_sources(&config.sources_dir);
+    let source_contents: HashMap<String, String> = sources
+        .iter()
+        .map(|(path, file)| (path.clone(), file.content.clone()))
+        .collect();
+    let imports_by_path: HashMap<String, Vec<String>> = source_contents
+        .iter()
+        .map(|(path, content)| (path.clone(), cache::extract_imports(content)))
+        .collect();
 
-    // Create standard-json input for solc
-    let evm_version = "byzantium";
-    let input = build_solc_input_json(&sources, &evm_version);
+    let settings_fingerprint = cache::settings_fingerprint(
+        &config.evm_version.to_string(),
+        config.optimizer.enabled,
+        config.optimizer.runs,
+        &config.output_selection,
+    );
 
-    // Compile & parse output
-    let raw_output = solc::compile(&input.to_string());
-    let output: SolcOutput = serde_json::from_str(&raw_output).unwrap();
+    // Group sources by the highest installed solc version whose pragma every file in their
+    // import closure agrees on, so incompatible contracts in the same tree don't force a
+    // single, possibly-unsatisfiable, compiler version on everything.
+    let installed = installed_versions();
+    let groups = resolve_groups(&source_contents, &installed, &config.remappings).unwrap_or_else(|unresolved| {
+        eprintln!(
+            "Error: no installed solc version satisfies the pragma for: {}",
+            unresolved.join(", ")
+        );
+        std::process::exit(1);
+    });
+    let resolved_solc_versions: HashMap<String, String> = groups
+        .iter()
+        .flat_map(|group| group.paths.iter().map(move |path| (path.clone(), group.version.to_string())))
+        .collect();
 
-    // Build & write artifacts
-    let artifacts: Vec<SolidityArtifact> = build_contract_schemas(&output, &sources);
-    let output_path = Path::new("./build/contracts/");
+    let output_path = Path::new(&config.output_dir);
     fs::create_dir_all(output_path).expect("Error creating output dir");
-    write_contract_schemas(&artifacts, &output_path);
+
+    // Load the cache from the previous run, discarding it entirely if the settings used to
+    // compile have changed since: a different optimizer config can change every artifact, not
+    // just the edited files. Per-file solc version changes are handled by `dirty_paths` below.
+    let cache_path = Path::new(cache::CACHE_PATH);
+    let mut previous_cache = cache::read_cache(cache_path);
+    if previous_cache.settings_fingerprint != settings_fingerprint {
+        previous_cache = SolFilesCache::new(settings_fingerprint.clone());
+    }
+
+    let dirty = cache::dirty_paths(
+        &source_contents,
+        &imports_by_path,
+        &previous_cache,
+        output_path,
+        &resolved_solc_versions,
+        &config.remappings,
+    );
+
+    // Compile & parse output, skipping version groups that have no dirty member. `resolve_groups`
+    // already puts a file in the same group as everything it imports (directly or transitively),
+    // so a group's `paths` *is* the import closure a dirty file needs solc to resolve - feeding
+    // solc only the dirty subset would leave its unchanged imports unresolvable. Each dirty group
+    // is compiled independently, in parallel, since groups are disjoint by construction.
+    let artifact_output = select_artifact_output();
+    let libraries = parse_libraries_env();
+    let artifacts: Vec<Artifact> = if dirty.is_empty() {
+        Vec::new()
+    } else {
+        let config_ref = &config;
+        let sources_ref = &sources;
+        let merged_output = thread::scope(|scope| {
+            let handles: Vec<_> = groups
+                .iter()
+                .filter_map(|group| {
+                    if !group.paths.iter().any(|path| dirty.contains(path)) {
+                        return None;
+                    }
+                    let group_sources: HashMap<String, SolidityFile> = group
+                        .paths
+                        .iter()
+                        .filter_map(|path| sources_ref.get(path).map(|file| (path.clone(), SolidityFile { content: file.content.clone() })))
+                        .collect();
+                    let version = group.version.to_string();
+                    Some(scope.spawn(move || {
+                        let input = build_solc_input_json(&group_sources, config_ref);
+                        let raw_output = solc::compile_with_version(&version, &input.to_string());
+                        let output: SolcOutput = serde_json::from_str(&raw_output).unwrap();
+                        output
+                    }))
+                })
+                .collect();
+            merge_solc_outputs(handles.into_iter().map(|handle| handle.join().unwrap()).collect())
+        });
+        if report_solc_errors(&merged_output.errors) {
+            eprintln!("Error: compilation failed");
+            std::process::exit(1);
+        }
+        build_contract_schemas(&merged_output, &sources, artifact_output.as_ref(), &libraries)
+    };
+
+    // Build & write artifacts
+    artifact_output.write_all(&artifacts, &output_path);
+
+    // Rewrite the cache: every file's entry is rebuilt from its current content, and files
+    // skipped this run carry their previous artifact list forward.
+    let mut artifact_names_by_path: HashMap<String, Vec<String>> = HashMap::new();
+    for artifact in &artifacts {
+        artifact_names_by_path
+            .entry(artifact.source_path.clone())
+            .or_insert_with(Vec::new)
+            .push(artifact.contract_name.clone());
+    }
+    let mut new_cache = SolFilesCache::new(settings_fingerprint);
+    for (path, content) in &source_contents {
+        let artifact_names = artifact_names_by_path.get(path).cloned().unwrap_or_else(|| {
+            previous_cache
+                .files
+                .get(path)
+                .map(|cached| cached.artifact_names.clone())
+                .unwrap_or_default()
+        });
+        let solc_version = resolved_solc_versions
+            .get(path)
+            .cloned()
+            .unwrap_or_default();
+        let cached_file = cache::build_cached_file(
+            Path::new(path),
+            path,
+            content,
+            &solc_version,
+            imports_by_path.get(path).cloned().unwrap_or_default(),
+            artifact_names,
+        );
+        new_cache.files.insert(path.clone(), cached_file);
+    }
+    cache::write_cache(&new_cache, cache_path);
 
     println!(
         "Compiled {} artifacts in {} seconds",
@@ -0,0 +1,308 @@
+use crate::cache::{extract_imports, resolve_import};
+use std::collections::{HashMap, HashSet};
+
+/// A bare `major.minor.patch` solc release, ordered so the "highest installed matching
+/// release" can be picked with a simple `max()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    /// Parses a bare `major.minor.patch`, tolerating the build metadata / prerelease suffix solc
+    /// appends to its own version string (e.g. `"0.8.19+commit.7dd6d404"`) by stopping at the
+    /// first `+` or `-` found on the patch segment.
+    pub fn parse(s: &str) -> Option<Version> {
+        let mut parts = s.trim().splitn(3, '.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch_segment = parts.next().unwrap_or("0");
+        let patch_digits = patch_segment
+            .find(['+', '-'])
+            .map_or(patch_segment, |end| &patch_segment[..end]);
+        let patch = patch_digits.parse().ok()?;
+        Some(Version { major, minor, patch })
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Comparator {
+    Exact(Version),
+    Gte(Version),
+    Gt(Version),
+    Lte(Version),
+    Lt(Version),
+    Caret(Version),
+}
+
+impl Comparator {
+    fn parse(term: &str) -> Option<Comparator> {
+        let term = term.trim();
+        if let Some(rest) = term.strip_prefix('^') {
+            return Version::parse(rest).map(Comparator::Caret);
+        }
+        if let Some(rest) = term.strip_prefix(">=") {
+            return Version::parse(rest).map(Comparator::Gte);
+        }
+        if let Some(rest) = term.strip_prefix("<=") {
+            return Version::parse(rest).map(Comparator::Lte);
+        }
+        if let Some(rest) = term.strip_prefix('>') {
+            return Version::parse(rest).map(Comparator::Gt);
+        }
+        if let Some(rest) = term.strip_prefix('<') {
+            return Version::parse(rest).map(Comparator::Lt);
+        }
+        if let Some(rest) = term.strip_prefix('=') {
+            return Version::parse(rest).map(Comparator::Exact);
+        }
+        Version::parse(term).map(Comparator::Exact)
+    }
+
+    fn matches(&self, v: &Version) -> bool {
+        match self {
+            Comparator::Exact(c) => v == c,
+            Comparator::Gte(c) => v >= c,
+            Comparator::Gt(c) => v > c,
+            Comparator::Lte(c) => v <= c,
+            Comparator::Lt(c) => v < c,
+            Comparator::Caret(c) => {
+                v >= c
+                    && if c.major > 0 {
+                        v.major == c.major
+                    } else if c.minor > 0 {
+                        v.major == 0 && v.minor == c.minor
+                    } else {
+                        v.major == 0 && v.minor == 0 && v.patch == c.patch
+                    }
+            }
+        }
+    }
+}
+
+/// A parsed `pragma solidity` constraint, e.g. `^0.8.0` or `>=0.7.0 <0.9.0`. Space-separated
+/// terms are AND'd together; `||` separates alternative ranges, either of which may match.
+#[derive(Debug, Clone)]
+pub struct VersionConstraint {
+    or_groups: Vec<Vec<Comparator>>,
+}
+
+impl VersionConstraint {
+    pub fn parse(pragma: &str) -> Option<VersionConstraint> {
+        let or_groups: Vec<Vec<Comparator>> = pragma
+            .split("||")
+            .map(|group| {
+                group
+                    .split_whitespace()
+                    .filter_map(Comparator::parse)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|group| !group.is_empty())
+            .collect();
+        if or_groups.is_empty() {
+            None
+        } else {
+            Some(VersionConstraint { or_groups })
+        }
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        self.or_groups
+            .iter()
+            .any(|group| group.iter().all(|comparator| comparator.matches(version)))
+    }
+}
+
+/// Extracts the `pragma solidity ...;` constraint string from a source file, if any.
+pub fn extract_pragma(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let trimmed = line.trim();
+        let rest = trimmed.strip_prefix("pragma solidity")?;
+        let constraint = rest.trim().trim_end_matches(';').trim();
+        if constraint.is_empty() {
+            None
+        } else {
+            Some(constraint.to_string())
+        }
+    })
+}
+
+/// A set of source paths that must be compiled together against a single resolved solc
+/// version, because they import one another (directly or transitively).
+#[derive(Debug)]
+pub struct VersionGroup {
+    pub version: Version,
+    pub paths: Vec<String>,
+}
+
+/// Groups `sources` into connected components of the import graph (files that import one
+/// another must be compiled in the same solc invocation) and resolves each component's
+/// `pragma solidity` constraints against `installed` to the highest satisfying release.
+///
+/// Returns the sorted paths of any component for which no installed version satisfies every
+/// constraint, so the caller can report a precise error instead of failing opaquely.
+pub fn resolve_groups(
+    sources: &HashMap<String, String>,
+    installed: &[Version],
+    remappings: &[String],
+) -> Result<Vec<VersionGroup>, Vec<String>> {
+    let mut adjacency: HashMap<String, HashSet<String>> = HashMap::new();
+    for path in sources.keys() {
+        adjacency.entry(path.clone()).or_default();
+    }
+    for (path, content) in sources {
+        for import in extract_imports(content) {
+            let resolved = resolve_import(path, &import, remappings);
+            if sources.contains_key(&resolved) {
+                adjacency.entry(path.clone()).or_default().insert(resolved.clone());
+                adjacency.entry(resolved).or_default().insert(path.clone());
+            }
+        }
+    }
+
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut components: Vec<Vec<String>> = Vec::new();
+    for path in sources.keys() {
+        if visited.contains(path) {
+            continue;
+        }
+        let mut component = Vec::new();
+        let mut queue = vec![path.clone()];
+        visited.insert(path.clone());
+        while let Some(current) = queue.pop() {
+            if let Some(neighbours) = adjacency.get(&current) {
+                for neighbour in neighbours {
+                    if visited.insert(neighbour.clone()) {
+                        queue.push(neighbour.clone());
+                    }
+                }
+            }
+            component.push(current);
+        }
+        component.sort();
+        components.push(component);
+    }
+
+    let mut groups = Vec::new();
+    let mut unresolved = Vec::new();
+    for component in components {
+        let constraints: Vec<VersionConstraint> = component
+            .iter()
+            .filter_map(|path| extract_pragma(&sources[path]))
+            .filter_map(|pragma| VersionConstraint::parse(&pragma))
+            .collect();
+
+        let resolved = installed
+            .iter()
+            .copied()
+            .filter(|version| constraints.iter().all(|constraint| constraint.matches(version)))
+            .max();
+
+        match resolved {
+            Some(version) => groups.push(VersionGroup { version, paths: component }),
+            None => unresolved.extend(component),
+        }
+    }
+
+    if unresolved.is_empty() {
+        groups.sort_by_key(|group| group.version);
+        Ok(groups)
+    } else {
+        unresolved.sort();
+        Err(unresolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parse_strips_build_metadata_and_prerelease_suffixes() {
+        assert_eq!(
+            Version::parse("0.8.19+commit.7dd6d404"),
+            Some(Version { major: 0, minor: 8, patch: 19 })
+        );
+        assert_eq!(
+            Version::parse("0.8.19-nightly.2023.1.1"),
+            Some(Version { major: 0, minor: 8, patch: 19 })
+        );
+        assert_eq!(Version::parse("0.8.19"), Some(Version { major: 0, minor: 8, patch: 19 }));
+    }
+
+    #[test]
+    fn caret_constraint_on_zero_major_matches_same_minor_only() {
+        let constraint = VersionConstraint::parse("^0.8.0").unwrap();
+        assert!(constraint.matches(&v("0.8.0")));
+        assert!(constraint.matches(&v("0.8.9")));
+        assert!(!constraint.matches(&v("0.7.9")));
+        assert!(!constraint.matches(&v("0.9.0")));
+    }
+
+    #[test]
+    fn and_and_or_groups_combine_as_expected() {
+        let constraint = VersionConstraint::parse(">=0.7.0 <0.8.0 || >=0.8.5").unwrap();
+        assert!(constraint.matches(&v("0.7.6")));
+        assert!(constraint.matches(&v("0.8.5")));
+        assert!(!constraint.matches(&v("0.8.0")));
+        assert!(!constraint.matches(&v("0.6.12")));
+    }
+
+    #[test]
+    fn resolve_groups_picks_highest_installed_version_per_component() {
+        let mut sources = HashMap::new();
+        sources.insert("A.sol".to_string(), "pragma solidity ^0.8.0;\n".to_string());
+        sources.insert("B.sol".to_string(), "pragma solidity ^0.7.0;\n".to_string());
+        let installed = vec![v("0.7.6"), v("0.8.0"), v("0.8.19")];
+
+        let groups = resolve_groups(&sources, &installed, &[]).unwrap();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].version, v("0.7.6"));
+        assert_eq!(groups[0].paths, vec!["B.sol".to_string()]);
+        assert_eq!(groups[1].version, v("0.8.19"));
+        assert_eq!(groups[1].paths, vec!["A.sol".to_string()]);
+    }
+
+    #[test]
+    fn resolve_groups_keeps_importers_and_imports_in_the_same_group() {
+        // Keys must already be in `resolve_import`'s normalized `./`-prefixed form, matching what
+        // `get_solidity_sources` hands `resolve_groups` in practice - otherwise the import never
+        // resolves to a key present in `sources` and the edge is silently dropped.
+        let mut sources = HashMap::new();
+        sources.insert(
+            "./A.sol".to_string(),
+            "pragma solidity ^0.8.0;\nimport \"./B.sol\";\n".to_string(),
+        );
+        sources.insert("./B.sol".to_string(), "pragma solidity ^0.8.0;\n".to_string());
+        let installed = vec![v("0.8.19")];
+
+        let groups = resolve_groups(&sources, &installed, &[]).unwrap();
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].paths, vec!["./A.sol".to_string(), "./B.sol".to_string()]);
+    }
+
+    #[test]
+    fn resolve_groups_reports_files_with_no_satisfying_installed_version() {
+        let mut sources = HashMap::new();
+        sources.insert("A.sol".to_string(), "pragma solidity ^0.5.0;\n".to_string());
+        let installed = vec![v("0.8.19")];
+
+        let err = resolve_groups(&sources, &installed, &[]).unwrap_err();
+
+        assert_eq!(err, vec!["A.sol".to_string()]);
+    }
+}